@@ -0,0 +1,91 @@
+// Автонастройка параметров блочности при первом вызове (polyalgorithm /
+// automatic block-size-tuning). Оптимальный размер блока и порог перехода
+// между `rust_mm_optimized` и `rust_mm_blocked` зависят от размера кэша
+// процессора и формы задачи, поэтому вместо жёстких констант калибруемся
+// один раз на нескольких представительных размерах и кэшируем победителя.
+
+use ndarray::Array2;
+use std::sync::{OnceLock, RwLock};
+use std::time::Instant;
+
+use crate::generic;
+
+const CANDIDATE_BLOCK_SIZES: [usize; 6] = [32, 48, 64, 96, 128, 256];
+const CALIBRATION_SIZES: [usize; 3] = [128, 256, 384];
+const THRESHOLD_CANDIDATES: [usize; 4] = [256, 384, 512, 768];
+
+#[derive(Clone, Copy)]
+pub struct CalibratedParams {
+    pub block_size: usize,
+    pub auto_threshold: usize,
+}
+
+static CACHE: OnceLock<RwLock<CalibratedParams>> = OnceLock::new();
+
+/// Возвращает закэшированные параметры, выполняя калибровку при первом обращении.
+pub fn params() -> CalibratedParams {
+    let cache = CACHE.get_or_init(|| RwLock::new(run_calibration()));
+    *cache.read().unwrap()
+}
+
+/// Принудительно пересчитывает параметры (для `rust_mm_calibrate`).
+pub fn force_recalibrate() -> CalibratedParams {
+    let fresh = run_calibration();
+    let cache = CACHE.get_or_init(|| RwLock::new(fresh));
+    *cache.write().unwrap() = fresh;
+    fresh
+}
+
+fn fill_matrix(size: usize) -> Array2<f64> {
+    Array2::from_shape_fn((size, size), |(i, j)| ((i * 31 + j * 17) % 13) as f64)
+}
+
+/// Мультиплицирует несколько представительных размеров матриц кандидатными
+/// размерами блоков, замеряет время и выбирает лучший размер блока, затем
+/// находит порог перехода, сравнивая ненаивный и блочный подходы. Замеряет
+/// те же `generic::optimized`/`generic::blocked`, на которые реально
+/// диспетчерит `rust_mm_auto`/`rust_mm_blocked`, а не отдельные
+/// реализации для калибровки — иначе порог не отражает реальный crossover.
+fn run_calibration() -> CalibratedParams {
+    let mut best_block_size = CANDIDATE_BLOCK_SIZES[0];
+    let mut best_total = std::time::Duration::MAX;
+
+    for &block_size in &CANDIDATE_BLOCK_SIZES {
+        let mut total = std::time::Duration::ZERO;
+        for &size in &CALIBRATION_SIZES {
+            let a = fill_matrix(size);
+            let b = fill_matrix(size);
+            let start = Instant::now();
+            let _ = generic::blocked(&a.view(), &b.view(), block_size);
+            total += start.elapsed();
+        }
+        if total < best_total {
+            best_total = total;
+            best_block_size = block_size;
+        }
+    }
+
+    let mut auto_threshold = *THRESHOLD_CANDIDATES.last().unwrap();
+    for &size in &THRESHOLD_CANDIDATES {
+        let a = fill_matrix(size);
+        let b = fill_matrix(size);
+
+        let start = Instant::now();
+        let _ = generic::optimized(&a.view(), &b.view());
+        let optimized_time = start.elapsed();
+
+        let start = Instant::now();
+        let _ = generic::blocked(&a.view(), &b.view(), best_block_size);
+        let blocked_time = start.elapsed();
+
+        if blocked_time <= optimized_time {
+            auto_threshold = size;
+            break;
+        }
+    }
+
+    CalibratedParams {
+        block_size: best_block_size,
+        auto_threshold,
+    }
+}
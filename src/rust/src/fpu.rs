@@ -0,0 +1,33 @@
+// Управление флагами flush-to-zero / denormals-are-zero вокруг горячих
+// циклов умножения матриц: денормализованные double's на несколько
+// порядков медленнее обычных и могут внезапно "обвалить" производительность
+// блочного/многопоточного GEMM, поэтому на время вычисления мы их запрещаем,
+// а по завершении восстанавливаем исходный режим FPU потока.
+
+#[cfg(target_arch = "x86_64")]
+const FTZ_DAZ_MASK: u32 = (1 << 15) | (1 << 6);
+
+/// Выполняет `f` с включёнными FTZ и DAZ, восстанавливая исходный MXCSR
+/// после завершения (в том числе при панике). На платформах без SSE
+/// MXCSR просто выполняет `f` как есть.
+#[cfg(target_arch = "x86_64")]
+pub fn with_flush_denormals<R>(f: impl FnOnce() -> R) -> R {
+    use std::arch::x86_64::{_mm_getcsr, _mm_setcsr};
+
+    struct RestoreCsr(u32);
+    impl Drop for RestoreCsr {
+        fn drop(&mut self) {
+            unsafe { _mm_setcsr(self.0) };
+        }
+    }
+
+    let original = unsafe { _mm_getcsr() };
+    let _restore = RestoreCsr(original);
+    unsafe { _mm_setcsr(original | FTZ_DAZ_MASK) };
+    f()
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+pub fn with_flush_denormals<R>(f: impl FnOnce() -> R) -> R {
+    f()
+}
@@ -0,0 +1,79 @@
+// Реализации `rust_mm_optimized`/`rust_mm_blocked`, параметризованные по
+// скалярному типу, чтобы f64 и f32 входные точки переиспользовали одну и
+// ту же логику column-major обработки и блокировки вместо дублирования кода.
+
+use ndarray::{Array2, ArrayView2, Axis};
+use num_traits::{Float, Zero};
+use rayon::prelude::*;
+
+use crate::fpu;
+
+/// Параллельная реализация без блокировки (каждая строка результата
+/// считается независимо и раздаётся по rayon).
+pub fn optimized<T>(a: &ArrayView2<T>, b: &ArrayView2<T>) -> Array2<T>
+where
+    T: Float + Zero + Send + Sync,
+{
+    let m = a.shape()[0];
+    let k = a.shape()[1];
+    let n = b.shape()[1];
+
+    let mut c = Array2::<T>::zeros((m, n));
+    c.axis_iter_mut(Axis(0))
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(i, mut row)| {
+            for j in 0..n {
+                let mut sum = T::zero();
+                for l in 0..k {
+                    sum = sum + a[[i, l]] * b[[l, j]];
+                }
+                row[j] = sum;
+            }
+        });
+    c
+}
+
+/// Блочная реализация, параллельная по j-блокам столбцов результата,
+/// с FTZ/DAZ включёнными на время вычисления.
+pub fn blocked<T>(a: &ArrayView2<T>, b: &ArrayView2<T>, block_size: usize) -> Array2<T>
+where
+    T: Float + Zero + Send + Sync,
+{
+    let m = a.shape()[0];
+    let k = a.shape()[1];
+    let n = b.shape()[1];
+
+    let mut c = Array2::<T>::zeros((m, n));
+    // rayon runs each block on a persistent worker thread with its own MXCSR,
+    // so FTZ/DAZ must be set from inside the closure that actually executes
+    // on that thread, not once on the calling thread before dispatch.
+    c.axis_chunks_iter_mut(Axis(1), block_size)
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(jb, mut c_block)| {
+            fpu::with_flush_denormals(|| {
+                let j_block = jb * block_size;
+                let j_end = std::cmp::min(j_block + block_size, n);
+
+                for i_block in (0..m).step_by(block_size) {
+                    let i_end = std::cmp::min(i_block + block_size, m);
+
+                    for k_block in (0..k).step_by(block_size) {
+                        let k_end = std::cmp::min(k_block + block_size, k);
+
+                        for i in i_block..i_end {
+                            for j in j_block..j_end {
+                                let mut sum = T::zero();
+                                for l in k_block..k_end {
+                                    sum = sum + a[[i, l]] * b[[l, j]];
+                                }
+                                c_block[[i, j - j_block]] = c_block[[i, j - j_block]] + sum;
+                            }
+                        }
+                    }
+                }
+            });
+        });
+    c
+}
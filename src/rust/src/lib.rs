@@ -1,8 +1,14 @@
-use libc::{c_double, c_int};
+use libc::{c_double, c_float, c_int};
 use ndarray::{Array2, ArrayView2};
-use rayon::prelude::*;
 use std::slice;
 
+mod calibrate;
+mod fpu;
+mod generic;
+mod microkernel;
+mod modular;
+mod pack;
+
 // Оптимизированная реализация умножения матриц на Rust
 // с использованием параллелизма и SIMD-оптимизаций
 #[no_mangle]
@@ -29,22 +35,8 @@ pub extern "C" fn rust_mm_optimized(
     let a = ArrayView2::from_shape((k, m), a_slice).unwrap().t();
     let b = ArrayView2::from_shape((n, k), b_slice).unwrap().t();
 
-    // Создаем результирующую матрицу
-    let mut c = Array2::zeros((m, n));
-
     // Используем параллельную обработку для умножения матриц
-    c.axis_iter_mut(ndarray::Axis(0))
-        .into_par_iter()
-        .enumerate()
-        .for_each(|(i, mut row)| {
-            for j in 0..n {
-                let mut sum = 0.0;
-                for l in 0..k {
-                    sum += a[[i, l]] * b[[l, j]];
-                }
-                row[j] = sum;
-            }
-        });
+    let c = generic::optimized(&a, &b);
 
     // Копируем результат обратно в C-массив
     // с учетом column-major формата R
@@ -55,7 +47,40 @@ pub extern "C" fn rust_mm_optimized(
     }
 }
 
-// Блочная реализация умножения матриц для больших матриц
+// Однопрецизионный (f32) вариант `rust_mm_optimized`, переиспользующий ту же
+// обобщённую реализацию из `generic`.
+#[no_mangle]
+pub extern "C" fn rust_mm_optimized_f32(
+    a_ptr: *const c_float,
+    b_ptr: *const c_float,
+    c_ptr: *mut c_float,
+    m: c_int,
+    k: c_int,
+    n: c_int,
+) {
+    let m = m as usize;
+    let k = k as usize;
+    let n = n as usize;
+
+    let a_slice = unsafe { slice::from_raw_parts(a_ptr, m * k) };
+    let b_slice = unsafe { slice::from_raw_parts(b_ptr, k * n) };
+    let c_slice = unsafe { slice::from_raw_parts_mut(c_ptr, m * n) };
+
+    let a = ArrayView2::from_shape((k, m), a_slice).unwrap().t();
+    let b = ArrayView2::from_shape((n, k), b_slice).unwrap().t();
+
+    let c = generic::optimized(&a, &b);
+
+    for i in 0..m {
+        for j in 0..n {
+            c_slice[i + j * m] = c[[i, j]];
+        }
+    }
+}
+
+// Блочная реализация умножения матриц для больших матриц.
+// Блоки по измерению j обрабатываются параллельно через rayon, так как
+// каждый воркер владеет непересекающимся набором столбцов `c`.
 #[no_mangle]
 pub extern "C" fn rust_mm_blocked(
     a_ptr: *const c_double,
@@ -80,35 +105,80 @@ pub extern "C" fn rust_mm_blocked(
     let a = ArrayView2::from_shape((k, m), a_slice).unwrap().t();
     let b = ArrayView2::from_shape((n, k), b_slice).unwrap().t();
 
-    // Создаем результирующую матрицу
-    let mut c = Array2::zeros((m, n));
+    // Размер блока подбирается калибровкой под конкретный CPU (см. `calibrate`)
+    let block_size = calibrate::params().block_size;
+    let c = generic::blocked(&a, &b, block_size);
 
-    // Определяем размер блока
-    const BLOCK_SIZE: usize = 64;
-
-    // Блочное умножение матриц
-    for i_block in (0..m).step_by(BLOCK_SIZE) {
-        let i_end = std::cmp::min(i_block + BLOCK_SIZE, m);
-        
-        for j_block in (0..n).step_by(BLOCK_SIZE) {
-            let j_end = std::cmp::min(j_block + BLOCK_SIZE, n);
-            
-            for k_block in (0..k).step_by(BLOCK_SIZE) {
-                let k_end = std::cmp::min(k_block + BLOCK_SIZE, k);
-                
-                // Умножение блоков матриц
-                for i in i_block..i_end {
-                    for j in j_block..j_end {
-                        let mut sum = 0.0;
-                        for l in k_block..k_end {
-                            sum += a[[i, l]] * b[[l, j]];
-                        }
-                        c[[i, j]] += sum;
-                    }
-                }
-            }
+    // Копируем результат обратно в C-массив
+    // с учетом column-major формата R
+    for i in 0..m {
+        for j in 0..n {
+            c_slice[i + j * m] = c[[i, j]];
         }
     }
+}
+
+// Однопрецизионный (f32) вариант `rust_mm_blocked`.
+#[no_mangle]
+pub extern "C" fn rust_mm_blocked_f32(
+    a_ptr: *const c_float,
+    b_ptr: *const c_float,
+    c_ptr: *mut c_float,
+    m: c_int,
+    k: c_int,
+    n: c_int,
+) {
+    let m = m as usize;
+    let k = k as usize;
+    let n = n as usize;
+
+    let a_slice = unsafe { slice::from_raw_parts(a_ptr, m * k) };
+    let b_slice = unsafe { slice::from_raw_parts(b_ptr, k * n) };
+    let c_slice = unsafe { slice::from_raw_parts_mut(c_ptr, m * n) };
+
+    let a = ArrayView2::from_shape((k, m), a_slice).unwrap().t();
+    let b = ArrayView2::from_shape((n, k), b_slice).unwrap().t();
+
+    let block_size = calibrate::params().block_size;
+    let c = generic::blocked(&a, &b, block_size);
+
+    for i in 0..m {
+        for j in 0..n {
+            c_slice[i + j * m] = c[[i, j]];
+        }
+    }
+}
+
+// Упакованный register-blocked микроядерный GEMM (BLIS/matrixmultiply-style).
+// Упаковывает панели `a` и `b` в непрерывную память и держит аккумулятор
+// MRxNR в регистрах, превращая memory-bound наивный цикл в compute-bound.
+#[no_mangle]
+pub extern "C" fn rust_mm_microkernel(
+    a_ptr: *const c_double,
+    b_ptr: *const c_double,
+    c_ptr: *mut c_double,
+    m: c_int,
+    k: c_int,
+    n: c_int,
+) {
+    // Преобразуем указатели в срезы Rust
+    let m = m as usize;
+    let k = k as usize;
+    let n = n as usize;
+
+    // Безопасно преобразуем указатели в срезы Rust
+    let a_slice = unsafe { slice::from_raw_parts(a_ptr, m * k) };
+    let b_slice = unsafe { slice::from_raw_parts(b_ptr, k * n) };
+    let c_slice = unsafe { slice::from_raw_parts_mut(c_ptr, m * n) };
+
+    // Создаем представления ndarray для матриц
+    // R хранит матрицы в формате column-major, поэтому учитываем это
+    let a = ArrayView2::from_shape((k, m), a_slice).unwrap().t();
+    let b = ArrayView2::from_shape((n, k), b_slice).unwrap().t();
+
+    // Создаем результирующую матрицу
+    let mut c = Array2::zeros((m, n));
+    microkernel::gemm(&a, &b, &mut c.view_mut());
 
     // Копируем результат обратно в C-массив
     // с учетом column-major формата R
@@ -129,7 +199,8 @@ pub extern "C" fn rust_mm_auto(
     k: c_int,
     n: c_int,
 ) {
-    if m <= 512 && k <= 512 && n <= 512 {
+    let threshold = calibrate::params().auto_threshold as c_int;
+    if m <= threshold && k <= threshold && n <= threshold {
         // Для малых матриц используем оптимизированный алгоритм
         rust_mm_optimized(a_ptr, b_ptr, c_ptr, m, k, n);
     } else {
@@ -137,3 +208,160 @@ pub extern "C" fn rust_mm_auto(
         rust_mm_blocked(a_ptr, b_ptr, c_ptr, m, k, n);
     }
 }
+
+// Однопрецизионный (f32) вариант `rust_mm_auto`.
+#[no_mangle]
+pub extern "C" fn rust_mm_auto_f32(
+    a_ptr: *const c_float,
+    b_ptr: *const c_float,
+    c_ptr: *mut c_float,
+    m: c_int,
+    k: c_int,
+    n: c_int,
+) {
+    let threshold = calibrate::params().auto_threshold as c_int;
+    if m <= threshold && k <= threshold && n <= threshold {
+        rust_mm_optimized_f32(a_ptr, b_ptr, c_ptr, m, k, n);
+    } else {
+        rust_mm_blocked_f32(a_ptr, b_ptr, c_ptr, m, k, n);
+    }
+}
+
+// Принудительно пересчитывает размер блока и порог `rust_mm_auto`, не дожидаясь
+// первого обращения. Полезно для R-пользователей, которые хотят
+// гарантированно свежую калибровку под текущую машину.
+#[no_mangle]
+pub extern "C" fn rust_mm_calibrate() {
+    calibrate::force_recalibrate();
+}
+
+// Умножение целочисленных матриц по модулю простого числа `modulus` с
+// редукцией Барретта (NTT-свёртки, решёточная криптография, коды,
+// исправляющие ошибки). Тот же column-major интерфейс, что и у остальных
+// функций.
+#[no_mangle]
+pub extern "C" fn rust_mm_mod_u64(
+    a_ptr: *const u64,
+    b_ptr: *const u64,
+    c_ptr: *mut u64,
+    m: c_int,
+    k: c_int,
+    n: c_int,
+    modulus: u64,
+) {
+    let m = m as usize;
+    let k = k as usize;
+    let n = n as usize;
+
+    let a_slice = unsafe { slice::from_raw_parts(a_ptr, m * k) };
+    let b_slice = unsafe { slice::from_raw_parts(b_ptr, k * n) };
+    let c_slice = unsafe { slice::from_raw_parts_mut(c_ptr, m * n) };
+
+    let a = ArrayView2::from_shape((k, m), a_slice).unwrap().t();
+    let b = ArrayView2::from_shape((n, k), b_slice).unwrap().t();
+
+    let c = modular::gemm_mod(&a, &b, modulus);
+
+    for i in 0..m {
+        for j in 0..n {
+            c_slice[i + j * m] = c[[i, j]];
+        }
+    }
+}
+
+// Упаковывает `b` один раз в k-контигуозный буфер, который затем
+// переиспользуется при умножении разных `a` на один и тот же `b`
+// (повторное применение одного линейного оператора). Возвращает
+// непрозрачный хендл; см.
+// `rust_mm_prepacked_b_free` и `rust_mm_with_prepacked_b`.
+#[no_mangle]
+pub extern "C" fn rust_mm_prepacked_b(
+    b_ptr: *const c_double,
+    k: c_int,
+    n: c_int,
+) -> *mut pack::PackedB {
+    let k_u = k as usize;
+    let n_u = n as usize;
+
+    let b_slice = unsafe { slice::from_raw_parts(b_ptr, k_u * n_u) };
+    let b = ArrayView2::from_shape((n_u, k_u), b_slice).unwrap().t();
+
+    let mut packed = pack::pack(&b);
+    let ptr = packed.as_mut_ptr();
+    std::mem::forget(packed);
+
+    Box::into_raw(Box::new(pack::PackedB { ptr, k, n }))
+}
+
+/// Освобождает буфер, выделенный `rust_mm_prepacked_b`.
+#[no_mangle]
+pub extern "C" fn rust_mm_prepacked_b_free(handle: *mut pack::PackedB) {
+    if handle.is_null() {
+        return;
+    }
+    let handle = unsafe { Box::from_raw(handle) };
+    let len = handle.k as usize * handle.n as usize;
+    unsafe {
+        drop(Vec::from_raw_parts(handle.ptr, len, len));
+    }
+}
+
+/// Умножает `a` на уже упакованный `b` (см. `rust_mm_prepacked_b`).
+#[no_mangle]
+pub extern "C" fn rust_mm_with_prepacked_b(
+    a_ptr: *const c_double,
+    handle: *const pack::PackedB,
+    c_ptr: *mut c_double,
+    m: c_int,
+) {
+    assert!(!handle.is_null(), "handle не должен быть null");
+    let handle = unsafe { &*handle };
+
+    let m_u = m as usize;
+    let k_u = handle.k as usize;
+    let n_u = handle.n as usize;
+
+    let a_slice = unsafe { slice::from_raw_parts(a_ptr, m_u * k_u) };
+    let c_slice = unsafe { slice::from_raw_parts_mut(c_ptr, m_u * n_u) };
+    let packed_slice = unsafe { slice::from_raw_parts(handle.ptr, k_u * n_u) };
+
+    let a = ArrayView2::from_shape((k_u, m_u), a_slice).unwrap().t();
+    let c = pack::multiply_with_packed(&a, packed_slice, k_u, n_u);
+
+    for i in 0..m_u {
+        for j in 0..n_u {
+            c_slice[i + j * m_u] = c[[i, j]];
+        }
+    }
+}
+
+// Блочный вариант `rust_mm_mod_u64` для больших целочисленных матриц.
+#[no_mangle]
+pub extern "C" fn rust_mm_mod_u64_blocked(
+    a_ptr: *const u64,
+    b_ptr: *const u64,
+    c_ptr: *mut u64,
+    m: c_int,
+    k: c_int,
+    n: c_int,
+    modulus: u64,
+) {
+    let m = m as usize;
+    let k = k as usize;
+    let n = n as usize;
+
+    let a_slice = unsafe { slice::from_raw_parts(a_ptr, m * k) };
+    let b_slice = unsafe { slice::from_raw_parts(b_ptr, k * n) };
+    let c_slice = unsafe { slice::from_raw_parts_mut(c_ptr, m * n) };
+
+    let a = ArrayView2::from_shape((k, m), a_slice).unwrap().t();
+    let b = ArrayView2::from_shape((n, k), b_slice).unwrap().t();
+
+    let c = modular::gemm_mod_blocked(&a, &b, modulus);
+
+    for i in 0..m {
+        for j in 0..n {
+            c_slice[i + j * m] = c[[i, j]];
+        }
+    }
+}
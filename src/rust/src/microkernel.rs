@@ -0,0 +1,124 @@
+// Упакованный register-blocked микроядерный GEMM в стиле BLIS/matrixmultiply.
+//
+// Идея: вместо наивного тройного цикла по `ArrayView2` упаковываем блок `a`
+// (MR строк x Kc столбцов) в непрерывную панель column-major и блок `b`
+// (Kc строк x NR столбцов) в непрерывную панель row-major, после чего
+// микроядро держит аккумулятор MRxNR в регистрах и проходит по измерению
+// Kc с FMA. Это превращает memory-bound наивный цикл в compute-bound.
+
+use ndarray::ArrayView2;
+
+// Размер микроядра: аккумулятор MR x NR должен помещаться в регистры.
+const MR: usize = 8;
+const NR: usize = 8;
+
+// Блокировка под L1/L2/L3: Kc - под L1, Mc - под L2, Nc - под L3.
+const KC: usize = 256;
+const MC: usize = 256;
+const NC: usize = 4096;
+
+/// Упаковывает блок `a[ic..ic+mc, pc..pc+kc]` в панель column-major,
+/// разбитую на полосы по MR строк (с нулевым дополнением хвоста).
+fn pack_a(a: &ArrayView2<f64>, ic: usize, mc: usize, pc: usize, kc: usize) -> Vec<f64> {
+    let panels = (mc + MR - 1) / MR;
+    let mut packed = vec![0.0; panels * MR * kc];
+    for p in 0..panels {
+        let row_base = ic + p * MR;
+        let rows_here = std::cmp::min(MR, mc - p * MR);
+        let dst = &mut packed[p * MR * kc..(p + 1) * MR * kc];
+        for l in 0..kc {
+            for r in 0..rows_here {
+                dst[l * MR + r] = a[[row_base + r, pc + l]];
+            }
+        }
+    }
+    packed
+}
+
+/// Упаковывает блок `b[pc..pc+kc, jc..jc+nc]` в панель row-major,
+/// разбитую на полосы по NR столбцов (с нулевым дополнением хвоста).
+fn pack_b(b: &ArrayView2<f64>, pc: usize, kc: usize, jc: usize, nc: usize) -> Vec<f64> {
+    let panels = (nc + NR - 1) / NR;
+    let mut packed = vec![0.0; panels * NR * kc];
+    for p in 0..panels {
+        let col_base = jc + p * NR;
+        let cols_here = std::cmp::min(NR, nc - p * NR);
+        let dst = &mut packed[p * NR * kc..(p + 1) * NR * kc];
+        for l in 0..kc {
+            for c in 0..cols_here {
+                dst[l * NR + c] = b[[pc + l, col_base + c]];
+            }
+        }
+    }
+    packed
+}
+
+/// Микроядро: обновляет плитку `c[ic..ic+mr, jc..jc+nr]` (mr<=MR, nr<=NR),
+/// держа аккумулятор MRxNR в регистрах и стримя по Kc с FMA.
+#[allow(clippy::too_many_arguments)]
+fn micro_kernel(
+    a_panel: &[f64],
+    b_panel: &[f64],
+    kc: usize,
+    c: &mut ndarray::ArrayViewMut2<f64>,
+    ic: usize,
+    jc: usize,
+    mr: usize,
+    nr: usize,
+) {
+    let mut acc = [[0.0f64; NR]; MR];
+    for l in 0..kc {
+        let a_col = &a_panel[l * MR..l * MR + MR];
+        let b_row = &b_panel[l * NR..l * NR + NR];
+        for i in 0..MR {
+            let a_val = a_col[i];
+            for j in 0..NR {
+                acc[i][j] = a_val.mul_add(b_row[j], acc[i][j]);
+            }
+        }
+    }
+    for i in 0..mr {
+        for j in 0..nr {
+            c[[ic + i, jc + j]] += acc[i][j];
+        }
+    }
+}
+
+/// Полный проход GEMM с классической вложенностью Goto: jc по Nc, pc по Kc,
+/// ic по Mc, затем микроядро MRxNR. `c` должна быть предварительно обнулена.
+pub fn gemm(a: &ArrayView2<f64>, b: &ArrayView2<f64>, c: &mut ndarray::ArrayViewMut2<f64>) {
+    let m = a.shape()[0];
+    let k = a.shape()[1];
+    let n = b.shape()[1];
+
+    for jc in (0..n).step_by(NC) {
+        let nc = std::cmp::min(NC, n - jc);
+        for pc in (0..k).step_by(KC) {
+            let kc = std::cmp::min(KC, k - pc);
+
+            // b_packed зависит только от (pc, jc), не от ic - пакуем один раз
+            // за пару (pc, jc) и переиспользуем для всех ic-блоков ниже.
+            let b_packed = pack_b(b, pc, kc, jc, nc);
+            let n_panels = (nc + NR - 1) / NR;
+
+            for ic in (0..m).step_by(MC) {
+                let mc = std::cmp::min(MC, m - ic);
+
+                let a_packed = pack_a(a, ic, mc, pc, kc);
+                let m_panels = (mc + MR - 1) / MR;
+
+                for jp in 0..n_panels {
+                    let j = jc + jp * NR;
+                    let nr = std::cmp::min(NR, nc - jp * NR);
+                    let b_panel = &b_packed[jp * NR * kc..(jp + 1) * NR * kc];
+                    for ip in 0..m_panels {
+                        let i = ic + ip * MR;
+                        let mr = std::cmp::min(MR, mc - ip * MR);
+                        let a_panel = &a_packed[ip * MR * kc..(ip + 1) * MR * kc];
+                        micro_kernel(a_panel, b_panel, kc, c, i, j, mr, nr);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+// Умножение целочисленных матриц над простым полем (mod q) с редукцией
+// Барретта. Нужно для NTT-свёрток, решёточной криптографии и кодов,
+// исправляющих ошибки, где операнды — целые числа по модулю q < 2^63.
+// Редукция Барретта убирает деление из горячего внутреннего цикла: вместо
+// `x % q` на каждое произведение считаем `r = x - floor(x*mu / 2^(2s)) * q`
+// и не более двух условных вычитаний, чтобы попасть в [0, q).
+
+use ndarray::{Array2, ArrayView2, Axis};
+use rayon::prelude::*;
+
+use crate::calibrate;
+
+/// Предвычисленные константы Барретта для фиксированного модуля `q`.
+#[derive(Clone, Copy)]
+pub struct Barrett {
+    q: u64,
+    mu: u128,
+    s: u32,
+}
+
+impl Barrett {
+    /// `s = ceil(log2(q))`, `mu = floor(2^(2s) / q)`.
+    pub fn new(q: u64) -> Self {
+        assert!(q > 1, "модуль должен быть больше 1");
+        // `s` может доходить до 63, и тогда `2*s = 126` всё ещё умещается в
+        // u128 без переполнения сдвига. При q > 2^63 (s = 64) `1u128 << 128`
+        // переполнил бы ширину типа, поэтому модули от 2^63 и выше запрещены явно
+        // (это совпадает с предпосылкой "операнды < q < 2^63" в описании).
+        assert!(q < (1u64 << 63), "модуль должен быть меньше 2^63");
+        let s = 64 - (q - 1).leading_zeros().min(63);
+        let mu = (1u128 << (2 * s)) / q as u128;
+        Barrett { q, mu, s }
+    }
+
+    /// Редуцирует произведение `x < q^2` в `[0, q)` без деления.
+    #[inline]
+    pub fn reduce(&self, x: u128) -> u64 {
+        let q1 = x >> (self.s - 1);
+        let t = (q1 * self.mu) >> (self.s + 1);
+        let qu = self.q as u128;
+        let mut r = x - t * qu;
+        if r >= qu {
+            r -= qu;
+        }
+        if r >= qu {
+            r -= qu;
+        }
+        r as u64
+    }
+
+    pub fn modulus(&self) -> u64 {
+        self.q
+    }
+}
+
+/// `Barrett::reduce` требует `x < q^2`, то есть оба множителя уже должны
+/// лежать в `[0, q)`. Эта предпосылка не проверяется внутри горячего
+/// цикла, поэтому валидируем её один раз на входе.
+fn assert_operands_reduced(a: &ArrayView2<u64>, b: &ArrayView2<u64>, q: u64) {
+    assert!(
+        a.iter().all(|&x| x < q),
+        "все элементы `a` должны быть < modulus"
+    );
+    assert!(
+        b.iter().all(|&x| x < q),
+        "все элементы `b` должны быть < modulus"
+    );
+}
+
+/// Наивный GEMM по модулю `q`: каждое произведение редуцируется по
+/// Барретту, частичные суммы аккумулируются в u128 и приводятся по модулю
+/// один раз на выходную ячейку (чтобы не переполнить аккумулятор при
+/// больших `k`, но не платить делением на каждое умножение).
+pub fn gemm_mod(a: &ArrayView2<u64>, b: &ArrayView2<u64>, modulus: u64) -> Array2<u64> {
+    let bp = Barrett::new(modulus);
+    assert_operands_reduced(a, b, bp.modulus());
+    let m = a.shape()[0];
+    let k = a.shape()[1];
+    let n = b.shape()[1];
+
+    let mut c = Array2::<u64>::zeros((m, n));
+    c.axis_iter_mut(Axis(0))
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(i, mut row)| {
+            for j in 0..n {
+                let mut acc: u128 = 0;
+                for l in 0..k {
+                    let prod = a[[i, l]] as u128 * b[[l, j]] as u128;
+                    acc += bp.reduce(prod) as u128;
+                }
+                row[j] = (acc % bp.modulus() as u128) as u64;
+            }
+        });
+    c
+}
+
+/// Блочный вариант `gemm_mod` для лучшей кэш-локальности на крупных матрицах.
+pub fn gemm_mod_blocked(a: &ArrayView2<u64>, b: &ArrayView2<u64>, modulus: u64) -> Array2<u64> {
+    let bp = Barrett::new(modulus);
+    assert_operands_reduced(a, b, bp.modulus());
+    let m = a.shape()[0];
+    let k = a.shape()[1];
+    let n = b.shape()[1];
+    let block_size = calibrate::params().block_size;
+
+    let mut c = Array2::<u64>::zeros((m, n));
+    c.axis_chunks_iter_mut(Axis(1), block_size)
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(jb, mut c_block)| {
+            let j_block = jb * block_size;
+            let j_end = std::cmp::min(j_block + block_size, n);
+
+            for i_block in (0..m).step_by(block_size) {
+                let i_end = std::cmp::min(i_block + block_size, m);
+
+                for k_block in (0..k).step_by(block_size) {
+                    let k_end = std::cmp::min(k_block + block_size, k);
+
+                    for i in i_block..i_end {
+                        for j in j_block..j_end {
+                            let mut acc: u128 = 0;
+                            for l in k_block..k_end {
+                                let prod = a[[i, l]] as u128 * b[[l, j]] as u128;
+                                acc += bp.reduce(prod) as u128;
+                            }
+                            let partial = (acc % bp.modulus() as u128) as u64;
+                            let cell = &mut c_block[[i, j - j_block]];
+                            *cell = (*cell + partial) % bp.modulus();
+                        }
+                    }
+                }
+            }
+        });
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_operands_reduced, Barrett};
+    use ndarray::arr2;
+
+    #[test]
+    fn reduces_correctly_for_a_representative_prime() {
+        let bp = Barrett::new(12289);
+        assert_eq!(bp.reduce(12288 * 12288), (12288u64 * 12288) % 12289);
+    }
+
+    #[test]
+    #[should_panic(expected = "меньше 2^63")]
+    fn rejects_modulus_at_or_above_2_pow_63() {
+        Barrett::new(1u64 << 63);
+    }
+
+    #[test]
+    #[should_panic(expected = "должны быть < modulus")]
+    fn rejects_operands_not_already_reduced() {
+        let a = arr2(&[[20 * 12289]]);
+        let b = arr2(&[[1]]);
+        assert_operands_reduced(&a.view(), &b.view(), 12289);
+    }
+}
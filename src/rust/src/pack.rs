@@ -0,0 +1,54 @@
+// Предварительная упаковка `b` в k-контигуозный (row-major по j) буфер.
+// Порядок буфера не зависит от шагов исходного представления `b`: для
+// фиксированного `j` элементы по измерению `k` лежат подряд. Для `b` из R
+// (column-major, форма k x n) он совпадает с исходным порядком.
+// Упаковываем один раз, чтобы при повторном применении одного и того же
+// линейного оператора (тот же `b`) ко многим `a` каждый вызов читал `b`
+// последовательно.
+
+use libc::c_double;
+use ndarray::{Array2, ArrayView2, Axis};
+use rayon::prelude::*;
+
+/// Непрозрачный хендл на упакованный буфер `b`: указатель + размеры.
+/// Должен быть освобождён через `rust_mm_prepacked_b_free`.
+#[repr(C)]
+pub struct PackedB {
+    pub ptr: *mut c_double,
+    pub k: i32,
+    pub n: i32,
+}
+
+/// Упаковывает `b` (форма k x n) в row-major по j буфер: для фиксированного
+/// `j` элементы `b[0..k, j]` лежат подряд.
+pub fn pack(b: &ArrayView2<f64>) -> Vec<f64> {
+    let k = b.shape()[0];
+    let n = b.shape()[1];
+    let mut packed = vec![0.0; k * n];
+    for j in 0..n {
+        for l in 0..k {
+            packed[j * k + l] = b[[l, j]];
+        }
+    }
+    packed
+}
+
+/// GEMM, читающий `b` из уже упакованного буфера вместо `ArrayView2`.
+pub fn multiply_with_packed(a: &ArrayView2<f64>, packed_b: &[f64], k: usize, n: usize) -> Array2<f64> {
+    let m = a.shape()[0];
+    let mut c = Array2::<f64>::zeros((m, n));
+    c.axis_iter_mut(Axis(0))
+        .into_par_iter()
+        .enumerate()
+        .for_each(|(i, mut row)| {
+            for j in 0..n {
+                let b_col = &packed_b[j * k..(j + 1) * k];
+                let mut sum = 0.0;
+                for l in 0..k {
+                    sum += a[[i, l]] * b_col[l];
+                }
+                row[j] = sum;
+            }
+        });
+    c
+}